@@ -0,0 +1,39 @@
+//! SHA-512 compression function: per-architecture backend dispatch.
+
+mod soft;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) use x86::compress;
+#[cfg(target_arch = "aarch64")]
+pub(crate) use aarch64::compress;
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) use soft::compress;
+
+// `compress_multi` has a native SIMD implementation on x86/x86_64 only; every
+// other target (aarch64 included, until it grows its own lane-parallel
+// backend) falls back to hashing each lane through `soft::compress` in turn.
+// Re-exporting both paths here, rather than leaving `compress_multi` buried
+// in the `x86` backend module, is what makes it reachable the same way
+// `compress` is.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use x86::{compress_multi, MULTI_LANES};
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub const MULTI_LANES: usize = 4;
+
+/// Hashes [`MULTI_LANES`] independent messages in lock-step, one block of
+/// each per call. Portable fallback for targets without a native multi-buffer
+/// backend: hashes each lane through [`soft::compress`] independently.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn compress_multi(states: &mut [[u64; 8]; MULTI_LANES], blocks: &[[[u8; 128]; MULTI_LANES]]) {
+    for round in blocks {
+        for lane in 0..MULTI_LANES {
+            soft::compress(&mut states[lane], core::slice::from_ref(&round[lane]));
+        }
+    }
+}
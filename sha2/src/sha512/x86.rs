@@ -12,11 +12,21 @@ use core::arch::x86_64::*;
 use crate::consts::{K64, K64X4};
 
 cpufeatures::new!(avx2_cpuid, "avx", "avx2", "sse2", "sse3");
+cpufeatures::new!(avx512_cpuid, "avx512f", "avx512bw");
+cpufeatures::new!(sha512ni_cpuid, "sha512", "avx2");
 
 pub fn compress(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
     // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
     // after stabilization
-    if avx2_cpuid::get() {
+    if sha512ni_cpuid::get() {
+        unsafe {
+            sha512_compress_x86_64_sha512ni(state, blocks);
+        }
+    } else if avx512_cpuid::get() {
+        unsafe {
+            sha512_compress_x86_64_avx512(state, blocks);
+        }
+    } else if avx2_cpuid::get() {
         unsafe {
             sha512_compress_x86_64_avx2(state, blocks);
         }
@@ -25,6 +35,368 @@ pub fn compress(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
     }
 }
 
+/// Number of independent messages [`compress_multi`] hashes side by side.
+pub const MULTI_LANES: usize = 4;
+
+/// Hashes [`MULTI_LANES`] independent messages in lock-step, one block of
+/// each per call, placing lane `j`'s state and message words in SIMD lane
+/// `j`. Unlike [`compress`], which works through sequential blocks of the
+/// *same* message, this amortizes a single set of rounds across unrelated
+/// streams, so throughput scales with lane count even when each message is
+/// too short to benefit from `compress` alone.
+///
+/// Re-exported from the parent `sha512` module, which falls back to a
+/// portable per-lane [`super::soft::compress`] loop on targets without this
+/// AVX2 implementation, the same way it picks between backends for
+/// [`compress`].
+pub fn compress_multi(states: &mut [[u64; 8]; MULTI_LANES], blocks: &[[[u8; 128]; MULTI_LANES]]) {
+    // TODO: Replace with https://github.com/rust-lang/rfcs/pull/2725
+    // after stabilization
+    if avx2_cpuid::get() {
+        unsafe {
+            compress_multi_x86_64_avx2(states, blocks);
+        }
+    } else {
+        compress_multi_soft(states, blocks);
+    }
+}
+
+fn compress_multi_soft(states: &mut [[u64; 8]; MULTI_LANES], blocks: &[[[u8; 128]; MULTI_LANES]]) {
+    for round in blocks {
+        for lane in 0..MULTI_LANES {
+            super::soft::compress(&mut states[lane], core::slice::from_ref(&round[lane]));
+        }
+    }
+}
+
+#[target_feature(enable = "avx,avx2,sse2,sse3")]
+unsafe fn compress_multi_x86_64_avx2(
+    states: &mut [[u64; 8]; MULTI_LANES],
+    blocks: &[[[u8; 128]; MULTI_LANES]],
+) {
+    macro_rules! rotr {
+        ($x:expr, $n:literal) => {
+            _mm256_or_si256(
+                _mm256_srli_epi64::<$n>($x),
+                _mm256_slli_epi64::<{ 64 - $n }>($x),
+            )
+        };
+    }
+
+    #[inline(always)]
+    unsafe fn xor3(a: __m256i, b: __m256i, c: __m256i) -> __m256i {
+        _mm256_xor_si256(_mm256_xor_si256(a, b), c)
+    }
+
+    #[inline(always)]
+    fn load_be_u64(block: &[u8; 128], word_idx: usize) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&block[word_idx * 8..word_idx * 8 + 8]);
+        u64::from_be_bytes(buf)
+    }
+
+    // One `__m256i` per word position, four lanes wide; every SHA-512
+    // operation below is applied element-wise with no cross-lane shuffles.
+    let mut s = [_mm256_setzero_si256(); 8];
+    for (i, s_i) in s.iter_mut().enumerate() {
+        let lane_words = [states[0][i], states[1][i], states[2][i], states[3][i]];
+        *s_i = _mm256_loadu_si256(lane_words.as_ptr() as *const _);
+    }
+
+    for round in blocks {
+        let saved = s;
+
+        let mut w = [_mm256_setzero_si256(); SHA512_ROUNDS_NUM];
+        for i in 0..SHA512_BLOCK_WORDS_NUM {
+            let lane_words = [
+                load_be_u64(&round[0], i),
+                load_be_u64(&round[1], i),
+                load_be_u64(&round[2], i),
+                load_be_u64(&round[3], i),
+            ];
+            w[i] = _mm256_loadu_si256(lane_words.as_ptr() as *const _);
+        }
+        for i in SHA512_BLOCK_WORDS_NUM..SHA512_ROUNDS_NUM {
+            let sigma0 = xor3(
+                rotr!(w[i - 15], 1),
+                rotr!(w[i - 15], 8),
+                _mm256_srli_epi64::<7>(w[i - 15]),
+            );
+            let sigma1 = xor3(
+                rotr!(w[i - 2], 19),
+                rotr!(w[i - 2], 61),
+                _mm256_srli_epi64::<6>(w[i - 2]),
+            );
+            w[i] = _mm256_add_epi64(
+                _mm256_add_epi64(w[i - 16], sigma0),
+                _mm256_add_epi64(w[i - 7], sigma1),
+            );
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = s;
+        for (i, &wi) in w.iter().enumerate() {
+            let big_sigma1 = xor3(rotr!(e, 14), rotr!(e, 18), rotr!(e, 41));
+            let ch = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+            let k = _mm256_set1_epi64x(K64[i] as i64);
+            let t1 = _mm256_add_epi64(
+                _mm256_add_epi64(h, big_sigma1),
+                _mm256_add_epi64(ch, _mm256_add_epi64(k, wi)),
+            );
+
+            let big_sigma0 = xor3(rotr!(a, 28), rotr!(a, 34), rotr!(a, 39));
+            let maj = xor3(
+                _mm256_and_si256(a, b),
+                _mm256_and_si256(a, c),
+                _mm256_and_si256(b, c),
+            );
+            let t2 = _mm256_add_epi64(big_sigma0, maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = _mm256_add_epi64(d, t1);
+            d = c;
+            c = b;
+            b = a;
+            a = _mm256_add_epi64(t1, t2);
+        }
+
+        s = [a, b, c, d, e, f, g, h];
+        for (s_i, saved_i) in s.iter_mut().zip(saved) {
+            *s_i = _mm256_add_epi64(*s_i, saved_i);
+        }
+    }
+
+    for (i, s_i) in s.iter().enumerate() {
+        let mut tmp = [0u64; 4];
+        _mm256_storeu_si256(tmp.as_mut_ptr() as *mut _, *s_i);
+        for (lane, state) in states.iter_mut().enumerate() {
+            state[i] = tmp[lane];
+        }
+    }
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+unsafe fn sha512_compress_x86_64_avx512(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    // The schedule is amortized across four blocks at a time, each living in
+    // one 128-bit quadrant of every `__m512i`; anything left over is handed
+    // to the AVX2/AVX single- and double-block routines.
+    let quad_blocks = blocks.len() / 4;
+    let processed = quad_blocks * 4;
+
+    let mut ms: MsgSchedule = Default::default();
+    let mut t2: RoundStates = [0u64; SHA512_ROUNDS_NUM];
+    let mut t3: RoundStates = [0u64; SHA512_ROUNDS_NUM];
+    let mut t4: RoundStates = [0u64; SHA512_ROUNDS_NUM];
+    let mut x = [_mm512_setzero_si512(); 8];
+
+    for i in (0..processed).step_by(4) {
+        load_data_avx512(
+            &mut x,
+            &mut ms,
+            &mut t2,
+            &mut t3,
+            &mut t4,
+            blocks.as_ptr().add(i) as *const _,
+        );
+
+        let mut current_state = *state;
+        rounds_0_63_avx512(
+            &mut current_state,
+            &mut x,
+            &mut ms,
+            &mut t2,
+            &mut t3,
+            &mut t4,
+        );
+        rounds_64_79(&mut current_state, &ms);
+        accumulate_state(state, &current_state);
+
+        for t in [&t2, &t3, &t4] {
+            current_state = *state;
+            process_second_block(&mut current_state, t);
+            accumulate_state(state, &current_state);
+        }
+    }
+
+    if processed < blocks.len() {
+        sha512_compress_x86_64_avx2(state, &blocks[processed..]);
+    }
+}
+
+#[inline(always)]
+unsafe fn load_data_avx512(
+    x: &mut [__m512i; 8],
+    ms: &mut MsgSchedule,
+    t2: &mut RoundStates,
+    t3: &mut RoundStates,
+    t4: &mut RoundStates,
+    data: *const u8,
+) {
+    #[allow(non_snake_case)]
+    let MASK = _mm512_set_epi32(
+        0x0809_0a0bu32 as i32,
+        0x0c0d_0e0fu32 as i32,
+        0x0001_0203u32 as i32,
+        0x0405_0607u32 as i32,
+        0x0809_0a0bu32 as i32,
+        0x0c0d_0e0fu32 as i32,
+        0x0001_0203u32 as i32,
+        0x0405_0607u32 as i32,
+        0x0809_0a0bu32 as i32,
+        0x0c0d_0e0fu32 as i32,
+        0x0001_0203u32 as i32,
+        0x0405_0607u32 as i32,
+        0x0809_0a0bu32 as i32,
+        0x0c0d_0e0fu32 as i32,
+        0x0001_0203u32 as i32,
+        0x0405_0607u32 as i32,
+    );
+
+    macro_rules! unrolled_iterations {
+        ($($i:literal),*) => {$(
+            let mut v = _mm512_setzero_si512();
+            v = _mm512_inserti32x4::<0>(v, _mm_loadu_si128(data.add(16 * $i) as *const _));
+            v = _mm512_inserti32x4::<1>(v, _mm_loadu_si128(data.add(128 + 16 * $i) as *const _));
+            v = _mm512_inserti32x4::<2>(v, _mm_loadu_si128(data.add(256 + 16 * $i) as *const _));
+            v = _mm512_inserti32x4::<3>(v, _mm_loadu_si128(data.add(384 + 16 * $i) as *const _));
+            x[$i] = _mm512_shuffle_epi8(v, MASK);
+
+            let y = _mm512_add_epi64(
+                x[$i],
+                _mm512_loadu_si512(&K64X8[8 * $i] as *const u64 as *const _),
+            );
+
+            _mm_store_si128(&mut ms[2 * $i] as *mut u64 as *mut _, _mm512_extracti32x4_epi32::<0>(y));
+            _mm_store_si128(&mut t2[2 * $i] as *mut u64 as *mut _, _mm512_extracti32x4_epi32::<1>(y));
+            _mm_store_si128(&mut t3[2 * $i] as *mut u64 as *mut _, _mm512_extracti32x4_epi32::<2>(y));
+            _mm_store_si128(&mut t4[2 * $i] as *mut u64 as *mut _, _mm512_extracti32x4_epi32::<3>(y));
+        )*};
+    }
+
+    unrolled_iterations!(0, 1, 2, 3, 4, 5, 6, 7);
+}
+
+#[inline(always)]
+unsafe fn rounds_0_63_avx512(
+    current_state: &mut State,
+    x: &mut [__m512i; 8],
+    ms: &mut MsgSchedule,
+    t2: &mut RoundStates,
+    t3: &mut RoundStates,
+    t4: &mut RoundStates,
+) {
+    let mut k64x8_idx: usize = 8 * (SHA512_BLOCK_WORDS_NUM / 2);
+
+    for i in 1..5 {
+        for j in 0..8 {
+            let y = sha512_update_x_avx512(x, &K64X8[k64x8_idx] as *const u64 as *const _);
+
+            sha_round(current_state, ms[2 * j]);
+            sha_round(current_state, ms[2 * j + 1]);
+
+            _mm_store_si128(
+                &mut ms[2 * j] as *mut u64 as *mut _,
+                _mm512_extracti32x4_epi32::<0>(y),
+            );
+            _mm_store_si128(
+                &mut t2[(16 * i) + 2 * j] as *mut u64 as *mut _,
+                _mm512_extracti32x4_epi32::<1>(y),
+            );
+            _mm_store_si128(
+                &mut t3[(16 * i) + 2 * j] as *mut u64 as *mut _,
+                _mm512_extracti32x4_epi32::<2>(y),
+            );
+            _mm_store_si128(
+                &mut t4[(16 * i) + 2 * j] as *mut u64 as *mut _,
+                _mm512_extracti32x4_epi32::<3>(y),
+            );
+
+            k64x8_idx += 8;
+        }
+    }
+}
+
+// `_mm256_alignr_epi8` shifts bytes independently within each 128-bit lane,
+// so it cannot produce a single 4-word (32-byte) window that straddles two
+// `__m256i` registers: it would leave qwords 1 and 3 of the result sourced
+// from the wrong lane. Build the true cross-lane window `{b[1], b[2], b[3],
+// a[0]}` out of two same-register 32-bit-lane permutes and a blend instead.
+#[inline(always)]
+#[target_feature(enable = "avx2")]
+unsafe fn shift_one_qword(a: __m256i, b: __m256i) -> __m256i {
+    let lo = _mm256_permutevar8x32_epi32(b, _mm256_setr_epi32(2, 3, 4, 5, 6, 7, 0, 1));
+    let hi = _mm256_permutevar8x32_epi32(a, _mm256_setr_epi32(0, 1, 0, 1, 0, 1, 0, 1));
+    _mm256_blend_epi32::<0b1100_0000>(lo, hi)
+}
+
+#[target_feature(enable = "sha512,avx2")]
+unsafe fn sha512_compress_x86_64_sha512ni(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    #[allow(non_snake_case)]
+    let MASK = _mm256_set_epi64x(
+        0x0809_0A0B_0C0D_0E0F_i64,
+        0x0001_0203_0405_0607_i64,
+        0x0809_0A0B_0C0D_0E0F_i64,
+        0x0001_0203_0405_0607_i64,
+    );
+
+    // {a, b, e, f} and {c, d, g, h}, highest qword first: `sha512rnds2`
+    // reads `a`/`c` from qword 3 down to `f`/`h` from qword 0, so the arrays
+    // below list the words in memory (lowest qword first) order.
+    let abef_init = [state[5], state[4], state[1], state[0]];
+    let cdgh_init = [state[7], state[6], state[3], state[2]];
+    let mut abef = _mm256_loadu_si256(abef_init.as_ptr() as *const _);
+    let mut cdgh = _mm256_loadu_si256(cdgh_init.as_ptr() as *const _);
+
+    for block in blocks {
+        let (abef_save, cdgh_save) = (abef, cdgh);
+
+        // Message schedule: 16 words straight from the block, the
+        // remaining 64 expanded four rounds' worth (4 words) at a time.
+        let mut w = [_mm256_setzero_si256(); 20];
+        for i in 0..4 {
+            let data = _mm256_loadu_si256(block.as_ptr().add(32 * i) as *const _);
+            w[i] = _mm256_shuffle_epi8(data, MASK);
+        }
+        for i in 4..20 {
+            let msg1 = _mm256_sha512msg1_epi64(w[i - 4], _mm256_castsi256_si128(w[i - 3]));
+            let added = _mm256_add_epi64(msg1, shift_one_qword(w[i - 1], w[i - 2]));
+            w[i] = _mm256_sha512msg2_epi64(added, w[i - 1]);
+        }
+
+        // `sha512rnds2` consumes one 128-bit (two-word) W+K half per call and
+        // advances the state by exactly one round-pair, so each of the 20
+        // four-word `w[i]` registers is split into its low and high halves
+        // and fed to two successive calls (40 calls * 2 rounds = 80 rounds).
+        for i in 0..20 {
+            let wk = _mm256_add_epi64(
+                w[i],
+                _mm256_loadu_si256(&K64[4 * i] as *const u64 as *const _),
+            );
+            let wk_lo = _mm256_castsi256_si128(wk);
+            let wk_hi = _mm256_extracti128_si256::<1>(wk);
+
+            cdgh = _mm256_sha512rnds2_epi64(cdgh, abef, wk_lo);
+            abef = _mm256_sha512rnds2_epi64(abef, cdgh, wk_hi);
+        }
+
+        abef = _mm256_add_epi64(abef, abef_save);
+        cdgh = _mm256_add_epi64(cdgh, cdgh_save);
+    }
+
+    let mut tmp = [0u64; 4];
+    _mm256_storeu_si256(tmp.as_mut_ptr() as *mut _, abef);
+    state[5] = tmp[0];
+    state[4] = tmp[1];
+    state[1] = tmp[2];
+    state[0] = tmp[3];
+    _mm256_storeu_si256(tmp.as_mut_ptr() as *mut _, cdgh);
+    state[7] = tmp[0];
+    state[6] = tmp[1];
+    state[3] = tmp[2];
+    state[2] = tmp[3];
+}
+
 #[target_feature(enable = "avx,avx2,sse2,sse3")]
 unsafe fn sha512_compress_x86_64_avx2(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
     let mut start_block = 0;
@@ -343,6 +715,22 @@ fn_sha512_update_x!(sha512_update_x_avx2, __m256i, {
         XOR = _mm256_xor_si256,
 });
 
+// `_mm512_loadu_si512` takes a `*const i32` for historical reasons; wrap it
+// so it fits the `$LOAD(k64_p)` call in `fn_sha512_update_x!` below.
+#[inline(always)]
+unsafe fn loadu_si512(p: *const __m512i) -> __m512i {
+    _mm512_loadu_si512(p as *const _)
+}
+
+fn_sha512_update_x!(sha512_update_x_avx512, __m512i, {
+        LOAD = loadu_si512,
+        ADD64 = _mm512_add_epi64,
+        ALIGNR8 = _mm512_alignr_epi8,
+        SRL64 = _mm512_srli_epi64,
+        SLL64 = _mm512_slli_epi64,
+        XOR = _mm512_xor_si512,
+});
+
 type State = [u64; SHA512_HASH_WORDS_NUM];
 type MsgSchedule = [u64; SHA512_BLOCK_WORDS_NUM];
 type RoundStates = [u64; SHA512_ROUNDS_NUM];
@@ -352,3 +740,158 @@ const SHA512_ROUNDS_NUM: usize = 80;
 const SHA512_HASH_BYTE_LEN: usize = 64;
 const SHA512_HASH_WORDS_NUM: usize = SHA512_HASH_BYTE_LEN / size_of::<u64>();
 const SHA512_BLOCK_WORDS_NUM: usize = SHA512_BLOCK_BYTE_LEN / size_of::<u64>();
+
+// `K64X4`-style table extended to 8 lanes: each `K[2i], K[2i+1]` pair is
+// replicated across all four 128-bit quadrants of a `__m512i`, one quadrant
+// per block in `sha512_compress_x86_64_avx512`'s four-block schedule.
+const K64X8: [u64; 8 * SHA512_ROUNDS_NUM / 2] = {
+    let mut out = [0u64; 8 * SHA512_ROUNDS_NUM / 2];
+    let mut i = 0;
+    while i < SHA512_ROUNDS_NUM / 2 {
+        let lo = K64[2 * i];
+        let hi = K64[2 * i + 1];
+        let mut lane = 0;
+        while lane < 4 {
+            out[8 * i + 2 * lane] = lo;
+            out[8 * i + 2 * lane + 1] = hi;
+            lane += 1;
+        }
+        i += 1;
+    }
+    out
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST test vector: SHA-512("abc"), padded into its single 128-byte block.
+    const IV: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+
+    const ABC_DIGEST: [u64; 8] = [
+        0xddaf35a193617aba,
+        0xcc417349ae204131,
+        0x12e6fa4e89a97ea2,
+        0x0a9eeee64b55d39a,
+        0x2192992a274fc1a8,
+        0x36ba3c23a3feebbd,
+        0x454d4423643ce80e,
+        0x2a9ac94fa54ca49f,
+    ];
+
+    fn abc_block() -> [u8; 128] {
+        let mut block = [0u8; 128];
+        block[0..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[127] = 0x18;
+        block
+    }
+
+    #[test]
+    fn sha512ni_matches_abc_digest() {
+        if !sha512ni_cpuid::get() {
+            return;
+        }
+
+        let mut state = IV;
+        unsafe {
+            sha512_compress_x86_64_sha512ni(&mut state, &[abc_block()]);
+        }
+        assert_eq!(state, ABC_DIGEST);
+    }
+
+    // A single `"abc"` block repeats too few distinct message words to shake
+    // out a message-schedule bug (the `sha512ni_matches_abc_digest` test
+    // above missed exactly that). Cross-check several *different* blocks
+    // against the already-established AVX2 path so the schedule expansion is
+    // actually exercised; this only runs where the ISA is present (real
+    // hardware or emulation), same caveat as the digest test above.
+    #[test]
+    fn sha512ni_matches_avx2_for_several_blocks() {
+        if !sha512ni_cpuid::get() || !avx2_cpuid::get() {
+            return;
+        }
+
+        let mut blocks = [abc_block(), abc_block(), abc_block()];
+        blocks[1][0] ^= 0xff;
+        blocks[2][64] ^= 0x01;
+
+        let mut state_ni = IV;
+        unsafe {
+            sha512_compress_x86_64_sha512ni(&mut state_ni, &blocks);
+        }
+
+        let mut state_avx2 = IV;
+        unsafe {
+            sha512_compress_x86_64_avx2(&mut state_avx2, &blocks);
+        }
+
+        assert_eq!(state_ni, state_avx2);
+    }
+
+    // The four-block AVX-512 path has no independent NIST vector to check
+    // against directly, so cross-check it against the already-established
+    // AVX2 path for the same four blocks.
+    #[test]
+    fn avx512_matches_avx2_for_four_blocks() {
+        if !avx512_cpuid::get() || !avx2_cpuid::get() {
+            return;
+        }
+
+        let blocks = [abc_block(), abc_block(), abc_block(), abc_block()];
+
+        let mut state_avx512 = IV;
+        unsafe {
+            sha512_compress_x86_64_avx512(&mut state_avx512, &blocks);
+        }
+
+        let mut state_avx2 = IV;
+        unsafe {
+            sha512_compress_x86_64_avx2(&mut state_avx2, &blocks);
+        }
+
+        assert_eq!(state_avx512, state_avx2);
+    }
+
+    #[test]
+    fn compress_multi_lanes_match_independent_abc_digest() {
+        let mut states = [IV; MULTI_LANES];
+        let blocks = [abc_block(); MULTI_LANES];
+
+        compress_multi_soft(&mut states, &[blocks]);
+        for state in states {
+            assert_eq!(state, ABC_DIGEST);
+        }
+    }
+
+    #[test]
+    fn compress_multi_avx2_matches_soft() {
+        if !avx2_cpuid::get() {
+            return;
+        }
+
+        let rounds = [
+            [abc_block(), abc_block(), abc_block(), abc_block()],
+            [abc_block(), abc_block(), abc_block(), abc_block()],
+        ];
+
+        let mut soft_states = [IV; MULTI_LANES];
+        compress_multi_soft(&mut soft_states, &rounds);
+
+        let mut avx2_states = [IV; MULTI_LANES];
+        unsafe {
+            compress_multi_x86_64_avx2(&mut avx2_states, &rounds);
+        }
+
+        assert_eq!(avx2_states, soft_states);
+    }
+}